@@ -0,0 +1,136 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `Command`s: typed messages that travel from a widget back up to the
+//! window (and, eventually, down into any widget interested in them).
+
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Identifies a kind of `Command`. Two `Selector`s are equal iff they were
+/// built from the same string literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selector(&'static str);
+
+impl Selector {
+    pub const fn new(s: &'static str) -> Selector {
+        Selector(s)
+    }
+}
+
+/// A message carrying a `Selector` plus an arbitrary, type-erased payload.
+#[derive(Clone)]
+pub struct Command {
+    pub selector: Selector,
+    payload: Arc<dyn Any>,
+}
+
+impl Command {
+    pub fn new<T: Any>(selector: Selector, payload: T) -> Command {
+        Command {
+            selector,
+            payload: Arc::new(payload),
+        }
+    }
+
+    /// Retrieve the payload, if it was built with type `T`.
+    pub fn get_object<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Command").field("selector", &self.selector).finish()
+    }
+}
+
+/// A single allowed file type in a file dialog, e.g. `FileSpec::SVG`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileSpec {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+impl FileSpec {
+    pub const SVG: FileSpec = FileSpec {
+        name: "SVG",
+        extensions: &["svg"],
+    };
+    pub const JPG: FileSpec = FileSpec {
+        name: "JPEG",
+        extensions: &["jpg", "jpeg"],
+    };
+}
+
+/// Options passed alongside `commands::SHOW_OPEN_PANEL` /
+/// `commands::SHOW_SAVE_PANEL`.
+#[derive(Debug, Clone, Default)]
+pub struct FileDialogOptions {
+    allowed_types: Vec<FileSpec>,
+    default_name: Option<String>,
+}
+
+impl FileDialogOptions {
+    pub fn new() -> FileDialogOptions {
+        FileDialogOptions::default()
+    }
+
+    pub fn allowed_types(mut self, types: Vec<FileSpec>) -> Self {
+        self.allowed_types = types;
+        self
+    }
+
+    pub fn default_name(mut self, name: impl Into<String>) -> Self {
+        self.default_name = Some(name.into());
+        self
+    }
+}
+
+/// The file chosen by the user, delivered via `commands::OPEN_FILE` /
+/// `commands::SAVE_FILE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    pub path: PathBuf,
+}
+
+/// Errors that can occur showing a platform dialog.
+#[derive(Debug)]
+pub struct PlatformError(pub String);
+
+impl std::fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "platform error: {}", self.0)
+    }
+}
+
+impl std::error::Error for PlatformError {}
+
+/// Well-known `Selector`s understood by every druid window.
+pub mod commands {
+    use super::Selector;
+
+    pub const QUIT_APP: Selector = Selector::new("druid-builtin.quit-app");
+    /// Payload: `FileDialogOptions`. Ask the platform to show an open panel;
+    /// the chosen file comes back as `OPEN_FILE`.
+    pub const SHOW_OPEN_PANEL: Selector = Selector::new("druid-builtin.show-open-panel");
+    /// Payload: `FileDialogOptions`. Ask the platform to show a save panel;
+    /// the chosen destination comes back as `SAVE_FILE`.
+    pub const SHOW_SAVE_PANEL: Selector = Selector::new("druid-builtin.show-save-panel");
+    /// Payload: `FileInfo`.
+    pub const OPEN_FILE: Selector = Selector::new("druid-builtin.open-file");
+    /// Payload: `FileInfo`.
+    pub const SAVE_FILE: Selector = Selector::new("druid-builtin.save-file-as");
+}
@@ -0,0 +1,296 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A generic, searchable command palette: register a name and a `Command`
+//! factory, and `CommandPalette` handles fuzzy-filtering and firing it.
+//!
+//! Any `AppLauncher`-built app can nest a `CommandPalette` in its widget
+//! tree the same way the bezier editor nests its `Toolbar`: build a
+//! `CommandRegistry`, register an entry per command, and drop a
+//! `WidgetPod<PaletteState, CommandPalette>` somewhere in the tree.
+
+use std::rc::Rc;
+
+use kurbo::{Rect, Size};
+use piet::{Color, RenderContext};
+
+use crate::command::Command;
+use crate::contexts::{EventCtx, LayoutCtx, PaintCtx, UpdateCtx};
+use crate::data::Data;
+use crate::env::Env;
+use crate::event::{Action, Event};
+use crate::keyboard::KeyCode;
+use crate::widget::{BoxConstraints, Widget};
+
+const ROW_HEIGHT: f64 = 24.0;
+const WIDTH: f64 = 320.0;
+const BG_COLOR: Color = Color::rgb24(0x20_20_20);
+const SELECTED_ROW_COLOR: Color = Color::rgb24(0x4a_90_d9);
+
+struct CommandEntry {
+    name: String,
+    factory: Rc<dyn Fn() -> Command>,
+    hit_count: u32,
+}
+
+/// Registered commands, each with a hit count that only grows when the
+/// command is actually *fired through the palette* (see `record_use`) —
+/// not when it's triggered by a keyboard shortcut the user already knows.
+/// This keeps the ranking biased towards commands people struggle to find.
+pub struct CommandRegistry {
+    entries: Vec<CommandEntry>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        CommandRegistry { entries: Vec::new() }
+    }
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: impl Into<String>, factory: impl Fn() -> Command + 'static) {
+        self.entries.push(CommandEntry {
+            name: name.into(),
+            factory: Rc::new(factory),
+            hit_count: 0,
+        });
+    }
+
+    /// Call when `name` is actually dispatched via the palette (as opposed
+    /// to its shortcut, if it has one).
+    pub fn record_use(&mut self, name: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.name == name) {
+            entry.hit_count += 1;
+        }
+    }
+
+    /// Entries matching `query`, sorted by fuzzy-match score first and
+    /// hit count second, so frequently-used commands float to the top of
+    /// equally-good matches.
+    fn ranked(&self, query: &str) -> Vec<&CommandEntry> {
+        let mut scored: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|e| fuzzy_score(query, &e.name).map(|score| (e, score)))
+            .collect();
+        scored.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.cmp(a_score).then(b.hit_count.cmp(&a.hit_count))
+        });
+        scored.into_iter().map(|(e, _)| e).collect()
+    }
+}
+
+/// A case-insensitive subsequence match: every character of `query` must
+/// appear in `candidate`, in order. The score rewards tighter matches
+/// (fewer skipped characters between hits) so "del" ranks "Delete Path"
+/// above "Duplicate Element" if both are present, say.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let query = query.to_lowercase();
+    let mut wanted = query.chars();
+    let mut next_wanted = wanted.next();
+    let mut score = 0i32;
+    let mut gap = 0i32;
+    for c in candidate.to_lowercase().chars() {
+        match next_wanted {
+            Some(w) if c == w => {
+                score -= gap;
+                gap = 0;
+                next_wanted = wanted.next();
+            }
+            _ => gap += 1,
+        }
+    }
+    if next_wanted.is_none() {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+/// The palette's own, small piece of app data: whether it's open, and the
+/// in-progress filter text. The `CommandRegistry` itself lives on the
+/// widget, not in `Data`, the same way `Toolbar`'s own transient state
+/// would.
+#[derive(Debug, Clone, Default)]
+pub struct PaletteState {
+    pub visible: bool,
+    pub query: String,
+}
+
+impl Data for PaletteState {
+    fn same(&self, other: &Self) -> bool {
+        self.visible == other.visible && self.query == other.query
+    }
+}
+
+pub struct CommandPalette {
+    registry: CommandRegistry,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new(registry: CommandRegistry) -> Self {
+        CommandPalette { registry, selected: 0 }
+    }
+}
+
+impl Widget<PaletteState> for CommandPalette {
+    fn paint(&mut self, paint_ctx: &mut PaintCtx, _base: &crate::widget::BaseState, data: &PaletteState, _env: &Env) {
+        if !data.visible {
+            return;
+        }
+        let matches = self.registry.ranked(&data.query);
+        let height = ROW_HEIGHT * (matches.len().max(1) as f64);
+        let bg = paint_ctx.render_ctx.solid_brush(BG_COLOR);
+        paint_ctx
+            .render_ctx
+            .fill(Rect::from_origin_size((0., 0.), Size::new(WIDTH, height)), &bg);
+
+        let selected_brush = paint_ctx.render_ctx.solid_brush(SELECTED_ROW_COLOR);
+        for (idx, _entry) in matches.iter().enumerate() {
+            if idx == self.selected {
+                let row = Rect::from_origin_size((0., idx as f64 * ROW_HEIGHT), Size::new(WIDTH, ROW_HEIGHT));
+                paint_ctx.render_ctx.fill(row, &selected_brush);
+            }
+        }
+        // Drawing the entry labels themselves needs a text layout API this
+        // minimal context doesn't expose yet; the highlighted row above at
+        // least shows which entry Enter would fire.
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, _bc: &BoxConstraints, data: &PaletteState, _env: &Env) -> Size {
+        if !data.visible {
+            return Size::ZERO;
+        }
+        let count = self.registry.ranked(&data.query).len().max(1);
+        Size::new(WIDTH, ROW_HEIGHT * count as f64)
+    }
+
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx, data: &mut PaletteState, _env: &Env) -> Option<Action> {
+        if !data.visible {
+            return None;
+        }
+        match event {
+            Event::KeyDown(key) if key.key_code == KeyCode::Escape => {
+                data.visible = false;
+                data.query.clear();
+                self.selected = 0;
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.key_code == KeyCode::ArrowDown => {
+                let count = self.registry.ranked(&data.query).len();
+                if count > 0 {
+                    self.selected = (self.selected + 1).min(count - 1);
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.key_code == KeyCode::ArrowUp => {
+                self.selected = self.selected.saturating_sub(1);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.key_code == KeyCode::Backspace => {
+                data.query.pop();
+                self.selected = 0;
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.key_code == KeyCode::Return => {
+                let fired = self.registry.ranked(&data.query).get(self.selected).map(|entry| {
+                    let command = (entry.factory)();
+                    (entry.name.clone(), command)
+                });
+                if let Some((name, command)) = fired {
+                    self.registry.record_use(&name);
+                    ctx.submit_command(command, None);
+                }
+                data.visible = false;
+                data.query.clear();
+                self.selected = 0;
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) => {
+                if let Some(text) = &key.text {
+                    data.query.push_str(text);
+                    self.selected = 0;
+                    ctx.set_handled();
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old: Option<&PaletteState>, _new: &PaletteState, _env: &Env) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Selector;
+
+    #[test]
+    fn fuzzy_score_requires_in_order_subsequence() {
+        assert!(fuzzy_score("dp", "Delete Path").is_some());
+        assert!(fuzzy_score("pd", "Delete Path").is_none());
+        assert!(fuzzy_score("xyz", "Delete Path").is_none());
+    }
+
+    #[test]
+    fn fuzzy_score_is_case_insensitive() {
+        assert!(fuzzy_score("DEL", "delete path").is_some());
+    }
+
+    #[test]
+    fn fuzzy_score_empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_score_rewards_tighter_matches() {
+        let tight = fuzzy_score("del", "Delete Path").unwrap();
+        let loose = fuzzy_score("del", "Duplicate Element").unwrap();
+        assert!(tight > loose, "a contiguous match should outscore a scattered one");
+    }
+
+    #[test]
+    fn ranked_sorts_by_score_then_hit_count() {
+        let mut registry = CommandRegistry::new();
+        registry.register("Delete Path", || Command::new(Selector::new("noop"), ()));
+        registry.register("Duplicate Element", || Command::new(Selector::new("noop"), ()));
+        registry.register("Select Tool", || Command::new(Selector::new("noop"), ()));
+
+        let names: Vec<_> = registry.ranked("del").iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Delete Path", "Duplicate Element"]);
+    }
+
+    #[test]
+    fn record_use_only_affects_the_named_entry() {
+        let mut registry = CommandRegistry::new();
+        registry.register("Pen Tool", || Command::new(Selector::new("noop"), ()));
+        registry.register("Select Tool", || Command::new(Selector::new("noop"), ()));
+        registry.record_use("Select Tool");
+        registry.record_use("Select Tool");
+
+        // Both fuzzy-match equally well against an empty query, so the
+        // higher hit count should float "Select Tool" to the top.
+        let names: Vec<_> = registry.ranked("").iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Select Tool", "Pen Tool"]);
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contexts passed to widgets during the `layout`, `after_layout`, `paint`,
+//! `event`, and `update` passes.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use kurbo::{Point, Rect, Vec2};
+use piet::Piet;
+
+use crate::command::Command;
+use crate::shell::window::WindowHandle;
+
+/// Identifies a window. `None` as a `submit_command` target means "the
+/// window the command originated in".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowId(pub(crate) u64);
+
+/// Identifies a focusable widget. Shares `WidgetPod`'s hitbox id rather than
+/// allocating its own counter, since both already need a stable per-widget
+/// identity assigned at the same place (`WidgetPod::new`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FocusId(pub(crate) u64);
+
+/// The focusable widgets registered so far this frame, in traversal order
+/// (which matches declaration order, so Tab/Shift-Tab cycle through the
+/// tree in the order widgets appear).
+///
+/// Shared the same way `HitboxList` is: built fresh by `after_layout` every
+/// frame, then consulted by `UiState` when handling Tab/Shift-Tab.
+#[derive(Debug, Clone, Default)]
+pub struct FocusList(Rc<RefCell<Vec<FocusId>>>);
+
+impl FocusList {
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    pub fn push(&self, id: FocusId) {
+        self.0.borrow_mut().push(id);
+    }
+
+    /// The focusable id that should gain focus after `current` on Tab,
+    /// wrapping around; `None` if nothing is focusable. `current` of `None`
+    /// (nothing focused yet) goes to the first.
+    pub fn next(&self, current: Option<FocusId>) -> Option<FocusId> {
+        let ids = self.0.borrow();
+        if ids.is_empty() {
+            return None;
+        }
+        let idx = current
+            .and_then(|id| ids.iter().position(|&i| i == id))
+            .map(|i| (i + 1) % ids.len())
+            .unwrap_or(0);
+        Some(ids[idx])
+    }
+
+    /// The focusable id before `current` on Shift-Tab, wrapping around.
+    pub fn prev(&self, current: Option<FocusId>) -> Option<FocusId> {
+        let ids = self.0.borrow();
+        if ids.is_empty() {
+            return None;
+        }
+        let idx = current
+            .and_then(|id| ids.iter().position(|&i| i == id))
+            .map(|i| if i == 0 { ids.len() - 1 } else { i - 1 })
+            .unwrap_or(ids.len() - 1);
+        Some(ids[idx])
+    }
+}
+
+/// An interactive region registered by a widget during `after_layout`.
+///
+/// `id` identifies the widget that owns the region (typically its
+/// `WidgetId`/hitbox id, distinct from application-level ids like
+/// `SelectionId`); `z_order` is the paint order index the owning
+/// `WidgetPod` was given. `HitboxList::hit_test` does not read `z_order`:
+/// overlaps are resolved by registration order, the last registered
+/// region winning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub rect: Rect,
+    pub id: u64,
+    pub z_order: usize,
+}
+
+/// The list of hitboxes registered so far this frame.
+///
+/// Shared (via `Rc<RefCell<_>>`) between the `LayoutCtx` used for
+/// `after_layout` and the `PaintCtx` used for `paint`, so that hover can be
+/// resolved against a single per-frame list that both passes see. It is
+/// cleared at the start of every `after_layout` pass and rebuilt from
+/// scratch before any painting or hover dispatch happens.
+#[derive(Debug, Clone, Default)]
+pub struct HitboxList(Rc<RefCell<Vec<Hitbox>>>);
+
+impl HitboxList {
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    pub fn push(&self, hitbox: Hitbox) {
+        self.0.borrow_mut().push(hitbox);
+    }
+
+    /// Hit-test `pos` against the registered hitboxes, in reverse paint
+    /// order, so that whatever was painted on top is preferred.
+    pub fn hit_test(&self, pos: Point) -> Option<u64> {
+        let boxes = self.0.borrow();
+        boxes
+            .iter()
+            .rev()
+            .find(|hb| hb.rect.contains(pos))
+            .map(|hb| hb.id)
+    }
+}
+
+/// Context passed to `Widget::layout` and `Widget::after_layout`.
+pub struct LayoutCtx {
+    hitboxes: HitboxList,
+    focusable: FocusList,
+    /// The id and paint-order index `WidgetPod` assigned to the widget
+    /// currently being visited, used to fill in `insert_hitbox`'s `Hitbox`.
+    current_id: u64,
+    current_z: usize,
+    /// The currently-visited widget's layout origin within its parent,
+    /// added to every hitbox it registers. Offsets of nested `WidgetPod`s
+    /// are not accumulated.
+    current_offset: Vec2,
+}
+
+impl LayoutCtx {
+    pub fn new(hitboxes: HitboxList, focusable: FocusList) -> Self {
+        LayoutCtx {
+            hitboxes,
+            focusable,
+            current_id: 0,
+            current_z: 0,
+            current_offset: Vec2::ZERO,
+        }
+    }
+
+    /// Called by `WidgetPod` before dispatching `after_layout`, so that any
+    /// hitbox the widget registers is tagged with its own id, a z-order
+    /// matching paint order, and offset into the parent's coordinate space.
+    pub fn set_current(&mut self, id: u64, z_order: usize, offset: Vec2) {
+        self.current_id = id;
+        self.current_z = z_order;
+        self.current_offset = offset;
+    }
+
+    /// Register an interactive region at `rect`, in this widget's own
+    /// coordinate space. `WidgetPod` is responsible for translating child
+    /// hitboxes into the parent's space as it does for `paint_with_offset`.
+    pub fn insert_hitbox(&mut self, rect: Rect) {
+        self.insert_hitbox_with_id(rect, self.current_id);
+    }
+
+    /// Like `insert_hitbox`, but tagged with `id` instead of the id of the
+    /// widget currently being visited. For a widget like `Canvas` that owns
+    /// many independently-hoverable regions (path points, selection
+    /// handles) which aren't themselves `WidgetPod`s, this is how each one
+    /// gets its own identity in the per-frame hitbox list.
+    pub fn insert_hitbox_with_id(&mut self, rect: Rect, id: u64) {
+        self.hitboxes.push(Hitbox {
+            rect: rect + self.current_offset,
+            id,
+            z_order: self.current_z,
+        });
+    }
+
+    /// Mark the widget currently being visited as eligible for Tab/Shift-Tab
+    /// focus traversal this frame. Called by `WidgetPod::after_layout` for
+    /// widgets whose `Widget::accepts_focus` returns `true`; widgets
+    /// themselves never need to call this.
+    pub fn register_focusable(&mut self) {
+        self.focusable.push(FocusId(self.current_id));
+    }
+}
+
+/// Context passed to `Widget::paint`.
+pub struct PaintCtx<'a, 'b: 'a> {
+    pub render_ctx: &'a mut Piet<'b>,
+    hitboxes: HitboxList,
+}
+
+impl<'a, 'b> PaintCtx<'a, 'b> {
+    pub fn new(render_ctx: &'a mut Piet<'b>, hitboxes: HitboxList) -> Self {
+        PaintCtx {
+            render_ctx,
+            hitboxes,
+        }
+    }
+
+    /// Whether `pos` currently hits the hitbox registered with `id`, per the
+    /// fully-rebuilt hitbox list for *this* frame.
+    pub fn is_hovered(&self, id: u64, pos: Point) -> bool {
+        self.hitboxes.hit_test(pos) == Some(id)
+    }
+}
+
+/// Context passed to `Widget::event`.
+pub struct EventCtx<'a> {
+    window: &'a WindowHandle,
+    window_id: WindowId,
+    handled: bool,
+    invalid: bool,
+    pending_commands: Vec<(Command, Option<WindowId>)>,
+    /// The id `WidgetPod::event` assigned to the widget currently being
+    /// visited, the same way `LayoutCtx::current_id` works for layout.
+    current_widget: FocusId,
+    /// The window's currently-focused widget, if any, as of the start of
+    /// this event. For the `Event::FocusChanged` dispatch specifically,
+    /// this is the *new* focus holder (the change has already taken
+    /// effect), so `is_focused()` tells the gaining widget it just gained
+    /// focus; for every other event it's simply unchanged for the
+    /// duration of the dispatch.
+    focused: Option<FocusId>,
+    /// The focus holder immediately before the current event, used only by
+    /// `was_focused()`. Equal to `focused` for every event except
+    /// `Event::FocusChanged`, where `UiState::set_focus` snapshots the old
+    /// holder here before updating `focused`, so the widget that just lost
+    /// focus can still recognize itself.
+    prev_focused: Option<FocusId>,
+    /// Set by `request_focus`/`resign_focus`; consumed by `UiState` after
+    /// dispatch to update `focused` and fire `Event::FocusChanged`.
+    /// `Some(None)` means "resign, don't focus anything else".
+    focus_change: Option<Option<FocusId>>,
+}
+
+impl<'a> EventCtx<'a> {
+    pub fn new(
+        window: &'a WindowHandle,
+        window_id: WindowId,
+        focused: Option<FocusId>,
+        prev_focused: Option<FocusId>,
+    ) -> Self {
+        EventCtx {
+            window,
+            window_id,
+            handled: false,
+            invalid: false,
+            pending_commands: Vec::new(),
+            current_widget: FocusId(0),
+            focused,
+            prev_focused,
+            focus_change: None,
+        }
+    }
+
+    pub fn set_handled(&mut self) {
+        self.handled = true;
+    }
+
+    pub fn is_handled(&self) -> bool {
+        self.handled
+    }
+
+    pub fn invalidate(&mut self) {
+        self.invalid = true;
+    }
+
+    pub fn window(&self) -> &WindowHandle {
+        self.window
+    }
+
+    pub fn window_id(&self) -> WindowId {
+        self.window_id
+    }
+
+    /// Queue `command` to be delivered to `target` (or, if `None`, back to
+    /// this same window) once the current event has finished dispatching.
+    pub fn submit_command(&mut self, command: Command, target: Option<WindowId>) {
+        self.pending_commands.push((command, target));
+    }
+
+    pub(crate) fn take_commands(&mut self) -> Vec<(Command, Option<WindowId>)> {
+        std::mem::take(&mut self.pending_commands)
+    }
+
+    /// Called by `WidgetPod::event` before dispatching to the widget it
+    /// wraps, so `request_focus`/`resign_focus`/`is_focused` apply to that
+    /// widget specifically.
+    pub(crate) fn set_current_widget(&mut self, id: FocusId) {
+        self.current_widget = id;
+    }
+
+    /// Ask to become the focused widget. Takes effect once the current
+    /// event has finished dispatching, at which point `Event::FocusChanged`
+    /// is sent to let the old and new focus holders update their
+    /// appearance.
+    pub fn request_focus(&mut self) {
+        self.focus_change = Some(Some(self.current_widget));
+    }
+
+    /// Give up focus. A no-op unless this widget currently holds it.
+    pub fn resign_focus(&mut self) {
+        if self.focused == Some(self.current_widget) {
+            self.focus_change = Some(None);
+        }
+    }
+
+    /// Whether the widget currently being visited holds focus.
+    pub fn is_focused(&self) -> bool {
+        self.focused == Some(self.current_widget)
+    }
+
+    /// Whether the widget currently being visited held focus immediately
+    /// before this event. Identical to `is_focused()` except while handling
+    /// `Event::FocusChanged`, where it lets the widget that just *lost*
+    /// focus recognize itself (`is_focused()` can't, since `focused` has
+    /// already moved to the new holder by the time that event is sent).
+    pub fn was_focused(&self) -> bool {
+        self.prev_focused == Some(self.current_widget)
+    }
+
+    pub(crate) fn take_focus_change(&mut self) -> Option<Option<FocusId>> {
+        self.focus_change.take()
+    }
+}
+
+/// Context passed to `Widget::update`.
+pub struct UpdateCtx<'a> {
+    window: &'a WindowHandle,
+    invalid: bool,
+}
+
+impl<'a> UpdateCtx<'a> {
+    pub fn new(window: &'a WindowHandle) -> Self {
+        UpdateCtx {
+            window,
+            invalid: false,
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.invalid = true;
+    }
+
+    pub fn window(&self) -> &WindowHandle {
+        self.window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hitbox_hit_test_prefers_topmost_on_overlap() {
+        let hitboxes = HitboxList::default();
+        hitboxes.push(Hitbox { rect: Rect::new(0., 0., 10., 10.), id: 1, z_order: 0 });
+        hitboxes.push(Hitbox { rect: Rect::new(5., 5., 15., 15.), id: 2, z_order: 1 });
+        assert_eq!(hitboxes.hit_test(Point::new(7., 7.)), Some(2));
+        assert_eq!(hitboxes.hit_test(Point::new(1., 1.)), Some(1));
+        assert_eq!(hitboxes.hit_test(Point::new(20., 20.)), None);
+    }
+
+    #[test]
+    fn hitbox_list_clear_resets_hit_testing() {
+        let hitboxes = HitboxList::default();
+        hitboxes.push(Hitbox { rect: Rect::new(0., 0., 10., 10.), id: 1, z_order: 0 });
+        hitboxes.clear();
+        assert_eq!(hitboxes.hit_test(Point::new(1., 1.)), None);
+    }
+
+    #[test]
+    fn focus_list_next_wraps_and_starts_at_first() {
+        let focusable = FocusList::default();
+        focusable.push(FocusId(1));
+        focusable.push(FocusId(2));
+        assert_eq!(focusable.next(None), Some(FocusId(1)));
+        assert_eq!(focusable.next(Some(FocusId(1))), Some(FocusId(2)));
+        assert_eq!(focusable.next(Some(FocusId(2))), Some(FocusId(1)));
+    }
+
+    #[test]
+    fn focus_list_prev_wraps_and_starts_at_last() {
+        let focusable = FocusList::default();
+        focusable.push(FocusId(1));
+        focusable.push(FocusId(2));
+        assert_eq!(focusable.prev(None), Some(FocusId(2)));
+        assert_eq!(focusable.prev(Some(FocusId(1))), Some(FocusId(2)));
+        assert_eq!(focusable.prev(Some(FocusId(2))), Some(FocusId(1)));
+    }
+
+    #[test]
+    fn focus_list_empty_has_no_next_or_prev() {
+        let focusable = FocusList::default();
+        assert_eq!(focusable.next(None), None);
+        assert_eq!(focusable.prev(None), None);
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `Data` trait, for fast comparison of application state.
+
+use std::sync::Arc;
+
+/// A trait for application data, supporting cheap equality comparison.
+///
+/// Implementors typically store shared state behind `Arc`, so that
+/// `same` can often be answered by pointer comparison instead of a deep
+/// structural walk.
+pub trait Data: Clone + 'static {
+    /// Determine whether two values are the same.
+    ///
+    /// This should agree with `PartialEq` where that is implemented, but
+    /// is allowed to be a conservative (cheaper) approximation: returning
+    /// `false` when the values are actually equal only costs a redundant
+    /// update, while returning `true` when they differ would cause a
+    /// missed update.
+    fn same(&self, other: &Self) -> bool;
+}
+
+impl<T: Data> Data for Arc<T> {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other) || T::same(self, other)
+    }
+}
+
+impl<T: Data> Data for Arc<Vec<T>> {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other)
+    }
+}
+
+macro_rules! impl_data_for_copy {
+    ($($ty:ty),+) => {
+        $(
+            impl Data for $ty {
+                fn same(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )+
+    };
+}
+
+impl_data_for_copy!(bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, char, ());
+
+impl Data for f64 {
+    fn same(&self, other: &Self) -> bool {
+        self.to_bits() == other.to_bits()
+    }
+}
+
+impl Data for String {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
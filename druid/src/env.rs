@@ -0,0 +1,29 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An environment used to pass resources (themes, fonts) down the widget tree.
+
+/// A container for theme values (colors, fonts, sizes) made available to
+/// every widget during `paint`, `layout`, and `event`.
+///
+/// This is currently a placeholder; widgets receive an `&Env` but are not
+/// required to look anything up in it.
+#[derive(Debug, Clone, Default)]
+pub struct Env {}
+
+impl Env {
+    pub fn default() -> Self {
+        Env {}
+    }
+}
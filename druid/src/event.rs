@@ -0,0 +1,66 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for events delivered to widgets.
+
+use kurbo::Point;
+
+use crate::command::Command;
+use crate::keyboard::{KeyEvent, KeyModifiers};
+
+/// A mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// A mouse event, as delivered to `Widget::event`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MouseEvent {
+    pub pos: Point,
+    pub button: MouseButton,
+    pub mods: KeyModifiers,
+}
+
+/// An event delivered to a widget.
+///
+/// Events are delivered depth-first: a container widget sees the event
+/// before its children do, and can mark it handled to stop propagation.
+#[derive(Debug, Clone)]
+pub enum Event {
+    MouseDown(MouseEvent),
+    MouseUp(MouseEvent),
+    MouseMoved(MouseEvent),
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    /// A `Command` routed to this window, e.g. the result of a file dialog
+    /// shown via `commands::SHOW_OPEN_PANEL`/`SHOW_SAVE_PANEL`.
+    Command(Command),
+    /// Sent to the whole tree whenever focus moves (including Tab/Shift-Tab
+    /// traversal and explicit `EventCtx::request_focus`/`resign_focus`
+    /// calls). A widget checks `EventCtx::is_focused()` (am I the new
+    /// holder?) together with `gained`, or `EventCtx::was_focused()` (was I
+    /// the old holder?) together with `lost`, to see whether it
+    /// specifically gained or lost focus, and invalidates if it wants to
+    /// change how it paints a focus ring.
+    FocusChanged { gained: bool, lost: bool },
+}
+
+/// The result of handling an `Event`.
+///
+/// This is a placeholder for the richer action/command types used
+/// elsewhere in druid; most widgets simply return `None`.
+pub type Action = ();
@@ -0,0 +1,66 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keyboard event types.
+
+/// A platform-independent key code.
+///
+/// This is a small subset covering the keys druid's own widgets care
+/// about; arbitrary text input still goes through `KeyEvent::text`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCode {
+    Escape,
+    Tab,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Backspace,
+    Return,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    KeyA,
+    KeyO,
+    KeyP,
+    KeyS,
+    KeyV,
+    KeyZ,
+    Unknown(u32),
+}
+
+/// Modifier keys held down alongside a `KeyEvent`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+    pub meta: bool,
+}
+
+/// A single keyboard event, as delivered to `Widget::event`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    pub key_code: KeyCode,
+    pub mods: KeyModifiers,
+    /// The resolved text for this key press, if any (e.g. for text input).
+    pub text: Option<String>,
+}
@@ -0,0 +1,41 @@
+// Copyright 2018 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Druid: a data-first Rust-native UI toolkit.
+
+pub use kurbo;
+pub use piet;
+pub use druid_shell as shell;
+
+mod command;
+mod command_palette;
+mod contexts;
+mod data;
+mod env;
+mod event;
+mod keyboard;
+mod undo;
+mod widget;
+mod window;
+
+pub use command::{commands, Command, FileDialogOptions, FileInfo, FileSpec, PlatformError, Selector};
+pub use command_palette::{CommandPalette, CommandRegistry, PaletteState};
+pub use contexts::{EventCtx, FocusId, FocusList, Hitbox, LayoutCtx, PaintCtx, UpdateCtx, WindowId};
+pub use data::Data;
+pub use env::Env;
+pub use event::{Action, Event, MouseButton, MouseEvent};
+pub use keyboard::{KeyCode, KeyEvent, KeyModifiers};
+pub use undo::UndoStack;
+pub use widget::{BaseState, BoxConstraints, Widget, WidgetPod};
+pub use window::{UiMain, UiState};
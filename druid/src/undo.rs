@@ -0,0 +1,128 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A reusable undo/redo history for `Data` whose mutations are cheap to
+//! snapshot (e.g. because the heavy parts are behind `Arc`, as
+//! `Data::same` already assumes).
+
+use crate::data::Data;
+
+/// A stack of past and future snapshots of some `Data` value `T`.
+///
+/// Pushing a snapshot is just a `T::clone()`; for the common case of a
+/// struct built from `Arc<_>` fields this only bumps refcounts, so it's
+/// cheap enough to call on every discrete edit.
+#[derive(Debug, Clone)]
+pub struct UndoStack<T: Data> {
+    past: Vec<T>,
+    future: Vec<T>,
+    /// A snapshot taken at the start of a potentially-multi-step edit
+    /// (e.g. a mouse drag), not yet pushed to `past`. See `begin_transaction`/`commit`.
+    pending: Option<T>,
+}
+
+impl<T: Data> Default for UndoStack<T> {
+    fn default() -> Self {
+        UndoStack {
+            past: Vec::new(),
+            future: Vec::new(),
+            pending: None,
+        }
+    }
+}
+
+impl<T: Data> UndoStack<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unconditionally record `before` as a single undoable step. Use this
+    /// for an edit that happens all at once (a keystroke, a delete).
+    pub fn record(&mut self, before: T) {
+        self.past.push(before);
+        self.future.clear();
+    }
+
+    /// Start coalescing a possibly-multi-step edit (a mouse drag): call
+    /// this once at `Mouse::Down`, then `commit` once at `Mouse::Up`. No
+    /// snapshot is pushed to `past` for the mouse-move events in between.
+    pub fn begin_transaction(&mut self, before: T) {
+        self.pending = Some(before);
+    }
+
+    /// Finish a transaction started with `begin_transaction`. If `current`
+    /// is the same as the snapshot taken at the start (per `Data::same`),
+    /// nothing actually changed and no undo step is recorded.
+    pub fn commit(&mut self, current: &T) {
+        if let Some(before) = self.pending.take() {
+            if !before.same(current) {
+                self.past.push(before);
+                self.future.clear();
+            }
+        }
+    }
+
+    /// Pop the most recent past snapshot, pushing `current` onto `future`
+    /// so `redo` can get back to it.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let prev = self.past.pop()?;
+        self.future.push(current);
+        Some(prev)
+    }
+
+    /// Pop the most recent future snapshot, pushing `current` onto `past`.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.future.pop()?;
+        self.past.push(current);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_then_undo_redo() {
+        let mut stack = UndoStack::new();
+        stack.record(1);
+        stack.record(2);
+        assert_eq!(stack.undo(3), Some(2));
+        assert_eq!(stack.undo(2), Some(1));
+        assert_eq!(stack.undo(1), None);
+        assert_eq!(stack.redo(1), Some(2));
+        assert_eq!(stack.redo(2), Some(3));
+    }
+
+    #[test]
+    fn transaction_commits_only_on_change() {
+        let mut stack = UndoStack::new();
+        stack.begin_transaction(1);
+        stack.commit(&1);
+        assert_eq!(stack.undo(1), None, "no-op transaction shouldn't record a step");
+
+        stack.begin_transaction(1);
+        stack.commit(&2);
+        assert_eq!(stack.undo(2), Some(1), "changed transaction should record one step");
+    }
+
+    #[test]
+    fn new_record_clears_redo_history() {
+        let mut stack = UndoStack::new();
+        stack.record(1);
+        assert_eq!(stack.undo(2), Some(1));
+        stack.record(2);
+        assert_eq!(stack.redo(2), None, "a fresh edit should drop the old future");
+    }
+}
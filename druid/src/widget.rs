@@ -0,0 +1,197 @@
+// Copyright 2018 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `Widget` trait and the `WidgetPod` container that drives it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use kurbo::{Rect, Size, Vec2};
+
+use crate::contexts::{EventCtx, FocusId, LayoutCtx, PaintCtx, UpdateCtx};
+use crate::data::Data;
+use crate::env::Env;
+use crate::event::{Action, Event};
+
+/// Per-widget layout state tracked by `WidgetPod`, and made available
+/// read-only to `Widget::paint` as `base_state`.
+#[derive(Debug, Clone, Default)]
+pub struct BaseState {
+    layout_rect: Rect,
+}
+
+impl BaseState {
+    pub fn layout_rect(&self) -> Rect {
+        self.layout_rect
+    }
+
+    pub fn size(&self) -> Size {
+        self.layout_rect.size()
+    }
+}
+
+/// Constraints on a widget's size, passed down from its parent's `layout`.
+#[derive(Debug, Clone, Copy)]
+pub struct BoxConstraints {
+    min: Size,
+    max: Size,
+}
+
+impl BoxConstraints {
+    pub fn new(min: Size, max: Size) -> Self {
+        BoxConstraints { min, max }
+    }
+
+    pub fn min(&self) -> Size {
+        self.min
+    }
+
+    pub fn max(&self) -> Size {
+        self.max
+    }
+}
+
+fn next_hitbox_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The core trait implemented by every widget.
+///
+/// The lifecycle for a single frame is `layout`, then `after_layout`, then
+/// `paint`: by the time `after_layout` runs, every widget's geometry for
+/// this frame is final, so the hitboxes it registers there describe
+/// up-to-date regions rather than last frame's. `event` and `update` are
+/// driven by the platform and by data changes respectively, independent of
+/// that per-frame sequence.
+pub trait Widget<T: Data> {
+    fn paint(&mut self, paint_ctx: &mut PaintCtx, base_state: &BaseState, data: &T, env: &Env);
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> Size;
+
+    /// Register this widget's interactive regions for the current frame.
+    ///
+    /// Called once per widget after `layout` has assigned final geometry,
+    /// and before `paint`. The default implementation registers nothing;
+    /// widgets with hit-testable regions (a draggable point, a toolbar
+    /// button) override this and call `ctx.insert_hitbox(rect)` for each
+    /// region, in their own coordinate space.
+    fn after_layout(&mut self, _ctx: &mut LayoutCtx, _data: &T, _env: &Env) {}
+
+    fn event(
+        &mut self,
+        event: &Event,
+        ctx: &mut EventCtx,
+        data: &mut T,
+        env: &Env,
+    ) -> Option<Action>;
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: Option<&T>, data: &T, env: &Env);
+
+    /// Whether this widget can receive keyboard focus.
+    ///
+    /// Defaults to `false`; widgets that want to participate in Tab/
+    /// Shift-Tab focus traversal override this to return `true`.
+    fn accepts_focus(&self) -> bool {
+        false
+    }
+}
+
+/// A container that wraps a widget together with the layout state `Widget`
+/// itself doesn't track: its rect within the parent, and the hitbox id used
+/// to tag whatever it registers during `after_layout`.
+pub struct WidgetPod<T: Data, W: Widget<T>> {
+    state: BaseState,
+    hitbox_id: u64,
+    inner: W,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
+    pub fn new(inner: W) -> Self {
+        WidgetPod {
+            state: BaseState::default(),
+            hitbox_id: next_hitbox_id(),
+            inner,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn set_layout_rect(&mut self, rect: Rect) {
+        self.state.layout_rect = rect;
+    }
+
+    pub fn layout_rect(&self) -> Rect {
+        self.state.layout_rect
+    }
+
+    pub fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    /// Drive this widget's `after_layout`, offsetting any hitbox it
+    /// registers by this widget's layout origin, the same way
+    /// `paint_with_offset` offsets drawing. `z_order` is recorded on each
+    /// registered `Hitbox`; hit-testing itself prefers whichever hitbox was
+    /// registered last, so siblings should be visited in paint order.
+    pub fn after_layout(&mut self, ctx: &mut LayoutCtx, data: &T, env: &Env, z_order: usize) {
+        let origin = self.state.layout_rect.origin();
+        let offset = Vec2::new(origin.x, origin.y);
+        ctx.set_current(self.hitbox_id, z_order, offset);
+        if self.inner.accepts_focus() {
+            ctx.register_focusable();
+        }
+        self.inner.after_layout(ctx, data, env);
+    }
+
+    pub fn paint_with_offset(&mut self, paint_ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let origin = self.state.layout_rect.origin();
+        paint_ctx
+            .render_ctx
+            .save()
+            .expect("save should always succeed");
+        paint_ctx
+            .render_ctx
+            .transform(kurbo::Affine::translate(Vec2::new(origin.x, origin.y)));
+        self.inner.paint(paint_ctx, &self.state, data, env);
+        paint_ctx
+            .render_ctx
+            .restore()
+            .expect("restore should always succeed");
+    }
+
+    pub fn event(
+        &mut self,
+        event: &Event,
+        ctx: &mut EventCtx,
+        data: &mut T,
+        env: &Env,
+    ) -> Option<Action> {
+        ctx.set_current_widget(FocusId(self.hitbox_id));
+        self.inner.event(event, ctx, data, env)
+    }
+
+    pub fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        self.inner.update(ctx, None, data, env);
+    }
+
+    pub fn hitbox_id(&self) -> u64 {
+        self.hitbox_id
+    }
+}
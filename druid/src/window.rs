@@ -0,0 +1,199 @@
+// Copyright 2018 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The root widget driver: owns the app's data and root widget, and runs
+//! the per-frame layout/after_layout/paint sequence.
+
+use kurbo::{Point, Size};
+use piet::Piet;
+
+use crate::command::{commands, Command, FileDialogOptions, FileInfo};
+use crate::contexts::{EventCtx, FocusId, FocusList, HitboxList, LayoutCtx, PaintCtx, UpdateCtx, WindowId};
+use crate::data::Data;
+use crate::env::Env;
+use crate::event::{Action, Event};
+use crate::keyboard::KeyCode;
+use crate::shell::window::WindowHandle;
+use crate::widget::{BoxConstraints, Widget, WidgetPod};
+
+/// Owns the root widget and application data for one window.
+pub struct UiState<T: Data, W: Widget<T>> {
+    root: WidgetPod<T, W>,
+    data: T,
+    env: Env,
+    hitboxes: HitboxList,
+    focusable: FocusList,
+    focused: Option<FocusId>,
+    active: bool,
+}
+
+impl<T: Data, W: Widget<T>> UiState<T, W> {
+    pub fn new(root: W, data: T) -> Self {
+        UiState {
+            root: WidgetPod::new(root),
+            data,
+            env: Env::default(),
+            hitboxes: HitboxList::default(),
+            focusable: FocusList::default(),
+            focused: None,
+            active: false,
+        }
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    /// Run one full frame: `layout`, then `after_layout` against freshly
+    /// cleared hitbox and focus lists, then `paint`. Hover, focus traversal,
+    /// and mouse dispatch for the *next* event consult the lists this
+    /// rebuilds, never a prior frame's.
+    fn run_frame(&mut self, render_ctx: &mut Piet, size: Size) {
+        self.hitboxes.clear();
+        self.focusable.clear();
+        let bc = BoxConstraints::new(Size::ZERO, size);
+        let mut layout_ctx = LayoutCtx::new(self.hitboxes.clone(), self.focusable.clone());
+        self.root.layout(&mut layout_ctx, &bc, &self.data, &self.env);
+        self.root.after_layout(&mut layout_ctx, &self.data, &self.env, 0);
+
+        let mut paint_ctx = PaintCtx::new(render_ctx, self.hitboxes.clone());
+        self.root.paint_with_offset(&mut paint_ctx, &self.data, &self.env);
+    }
+
+    /// Resolve hover for `pos` against this frame's registered hitboxes.
+    pub fn hit_test(&self, pos: Point) -> Option<u64> {
+        self.hitboxes.hit_test(pos)
+    }
+
+    fn dispatch(&mut self, event: &Event, window: &WindowHandle) -> Option<Action> {
+        self.dispatch_with_prev_focus(event, window, self.focused)
+    }
+
+    /// The actual dispatch logic; `prev_focused` is `self.focused` for
+    /// every caller except `set_focus`, which passes the focus holder from
+    /// *before* it updated `self.focused`, so the widget handling
+    /// `Event::FocusChanged` can tell whether it specifically is the one
+    /// that lost focus (see `EventCtx::was_focused`).
+    fn dispatch_with_prev_focus(
+        &mut self,
+        event: &Event,
+        window: &WindowHandle,
+        prev_focused: Option<FocusId>,
+    ) -> Option<Action> {
+        let mut ctx = EventCtx::new(window, WindowId(0), self.focused, prev_focused);
+        let action = self.root.event(event, &mut ctx, &mut self.data, &self.env);
+        let mut update_ctx = UpdateCtx::new(window);
+        self.root.update(&mut update_ctx, &self.data, &self.env);
+
+        // Keyboard events go to the root like every other event; they are
+        // not routed to the focused widget first, so it sees a key only if
+        // its ancestors pass it on. Tab/Shift-Tab traversal is the fallback
+        // and only fires if nothing in that dispatch claimed the key.
+        let focus_change = match (ctx.is_handled(), event) {
+            (false, Event::KeyDown(key)) if key.key_code == KeyCode::Tab && key.mods.shift => {
+                Some(self.focusable.prev(self.focused))
+            }
+            (false, Event::KeyDown(key)) if key.key_code == KeyCode::Tab => {
+                Some(self.focusable.next(self.focused))
+            }
+            _ => ctx.take_focus_change(),
+        };
+        if let Some(new_focus) = focus_change {
+            self.set_focus(new_focus, window);
+        }
+
+        for (command, _target) in ctx.take_commands() {
+            match self.run_builtin_command(&command, window) {
+                BuiltinCommand::NotRecognized => {
+                    self.dispatch(&Event::Command(command), window);
+                }
+                BuiltinCommand::Handled(Some(result)) => {
+                    self.dispatch(&Event::Command(result), window);
+                }
+                BuiltinCommand::Handled(None) => {}
+            }
+        }
+        action
+    }
+
+    /// Move focus to `new_focus`, notifying the tree via
+    /// `Event::FocusChanged` so the old and new focus holders can
+    /// invalidate their appearance.
+    fn set_focus(&mut self, new_focus: Option<FocusId>, window: &WindowHandle) {
+        if new_focus == self.focused {
+            return;
+        }
+        let old_focused = self.focused;
+        let lost = old_focused.is_some();
+        let gained = new_focus.is_some();
+        self.focused = new_focus;
+        self.dispatch_with_prev_focus(&Event::FocusChanged { gained, lost }, window, old_focused);
+    }
+
+    /// Handle the window-level commands druid itself understands (file
+    /// dialogs), translating them into the follow-up command (`OPEN_FILE`/
+    /// `SAVE_FILE`) the app actually wants.
+    fn run_builtin_command(&self, command: &Command, window: &WindowHandle) -> BuiltinCommand {
+        if command.selector == commands::SHOW_OPEN_PANEL {
+            let opts = command
+                .get_object::<FileDialogOptions>()
+                .cloned()
+                .unwrap_or_default();
+            let follow_up = window
+                .show_open_panel(opts)
+                .map(|path| Command::new(commands::OPEN_FILE, FileInfo { path }));
+            BuiltinCommand::Handled(follow_up)
+        } else if command.selector == commands::SHOW_SAVE_PANEL {
+            let opts = command
+                .get_object::<FileDialogOptions>()
+                .cloned()
+                .unwrap_or_default();
+            let follow_up = window
+                .show_save_panel(opts)
+                .map(|path| Command::new(commands::SAVE_FILE, FileInfo { path }));
+            BuiltinCommand::Handled(follow_up)
+        } else {
+            BuiltinCommand::NotRecognized
+        }
+    }
+}
+
+/// The result of `UiState::run_builtin_command`, distinguishing "not one of
+/// druid's own selectors" from "recognized, but this particular invocation
+/// produced no follow-up command" (the user cancelled the file dialog) —
+/// the two cases need opposite handling (forward the original command vs.
+/// do nothing), so a plain `Option<Command>` can't tell them apart.
+enum BuiltinCommand {
+    NotRecognized,
+    Handled(Option<Command>),
+}
+
+/// Adapts a `UiState` to the platform window handler interface.
+pub struct UiMain<T: Data, W: Widget<T>> {
+    state: UiState<T, W>,
+}
+
+impl<T: Data, W: Widget<T>> UiMain<T, W> {
+    pub fn new(state: UiState<T, W>) -> Self {
+        UiMain { state }
+    }
+
+    pub fn paint(&mut self, render_ctx: &mut Piet, size: Size) {
+        self.state.run_frame(render_ctx, size);
+    }
+
+    pub fn handle_event(&mut self, event: &Event, window: &WindowHandle) -> Option<Action> {
+        self.state.dispatch(event, window)
+    }
+}
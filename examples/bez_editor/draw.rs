@@ -0,0 +1,79 @@
+//! Rendering for paths and the current selection.
+
+use std::sync::Arc;
+
+use druid::kurbo::{BezPath, Circle, Point, Rect};
+use druid::piet::{Color, RenderContext};
+use druid::PaintCtx;
+
+use crate::path::Path;
+use crate::SelectionId;
+
+const PATH_COLOR: Color = Color::rgb24(0x30_30_30);
+const POINT_COLOR: Color = Color::rgb24(0x30_30_30);
+const SELECTED_POINT_COLOR: Color = Color::rgb24(0x4a_90_d9);
+const HOVERED_POINT_COLOR: Color = Color::rgb24(0x8a_b8_e8);
+const MARQUEE_COLOR: Color = Color::rgba32(0x4a_90_d9_60);
+const POINT_RADIUS: f64 = 4.0;
+const SELECTED_POINT_RADIUS: f64 = 5.0;
+const HOVERED_POINT_RADIUS: f64 = 5.0;
+
+pub(crate) fn draw_paths(
+    paths: &Arc<Vec<Path>>,
+    selection: &Arc<Vec<SelectionId>>,
+    marquee: Option<Rect>,
+    hover_pos: Option<Point>,
+    paint_ctx: &mut PaintCtx,
+) {
+    let path_brush = paint_ctx.render_ctx.solid_brush(PATH_COLOR);
+    let point_brush = paint_ctx.render_ctx.solid_brush(POINT_COLOR);
+    let selected_brush = paint_ctx.render_ctx.solid_brush(SELECTED_POINT_COLOR);
+    let hovered_brush = paint_ctx.render_ctx.solid_brush(HOVERED_POINT_COLOR);
+
+    for (path_idx, path) in paths.iter().enumerate() {
+        let points = path.points();
+        if points.is_empty() {
+            continue;
+        }
+
+        let mut bez = BezPath::new();
+        bez.move_to(points[0].point);
+        for window in points.windows(2) {
+            let (prev, next) = (window[0], window[1]);
+            match prev.trailing_handle {
+                Some(handle) => bez.quad_to(handle, next.point),
+                None => bez.line_to(next.point),
+            }
+        }
+        paint_ctx.render_ctx.stroke(&bez, &path_brush, 1.0);
+
+        for p in points {
+            let is_selected = selection
+                .iter()
+                .any(|sel| sel.path_idx == path_idx && sel.point_id == p.id);
+            // Hover-tested against this frame's hitboxes (registered by
+            // `Canvas::after_layout`), not a geometry snapshot from a
+            // previous frame, so a point that just moved under the cursor
+            // during a drag highlights correctly instead of flickering.
+            let is_hovered = !is_selected
+                && hover_pos.map_or(false, |pos| {
+                    paint_ctx.is_hovered(p.id.to_hitbox_id(path_idx), pos)
+                });
+            let (brush, radius) = if is_selected {
+                (&selected_brush, SELECTED_POINT_RADIUS)
+            } else if is_hovered {
+                (&hovered_brush, HOVERED_POINT_RADIUS)
+            } else {
+                (&point_brush, POINT_RADIUS)
+            };
+            let circle = Circle::new(p.point, radius);
+            paint_ctx.render_ctx.fill(circle, brush);
+        }
+    }
+
+    if let Some(rect) = marquee {
+        let marquee_brush = paint_ctx.render_ctx.solid_brush(MARQUEE_COLOR);
+        paint_ctx.render_ctx.fill(rect, &marquee_brush);
+        paint_ctx.render_ctx.stroke(rect, &path_brush, 1.0);
+    }
+}
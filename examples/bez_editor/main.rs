@@ -14,58 +14,140 @@
 
 //! A simple bezier path editor.
 
-use druid::kurbo::{Point, Rect, Size};
+use druid::kurbo::{Point, Rect, Size, Vec2};
 use druid::piet::{Color, RenderContext};
 use druid::shell::window::Cursor;
 use druid::shell::{runloop, WindowBuilder};
 use std::sync::Arc;
 
 use druid::{
-    Action, BaseState, BoxConstraints, Data, Env, Event, EventCtx, KeyCode, LayoutCtx, PaintCtx,
-    UiMain, UiState, UpdateCtx, Widget, WidgetPod,
+    commands, Action, BaseState, BoxConstraints, Command, CommandPalette, CommandRegistry, Data,
+    Env, Event, EventCtx, FileDialogOptions, FileInfo, FileSpec, KeyCode, LayoutCtx, PaletteState,
+    PaintCtx, Selector, UiMain, UiState, UndoStack, UpdateCtx, Widget, WidgetPod,
 };
 
 mod draw;
 mod path;
 mod pen;
+mod select;
+mod svg;
 mod toolbar;
 
 use draw::draw_paths;
 use path::{Path, PointId};
 use pen::Pen;
+use select::Select;
+use svg::SvgError;
 use toolbar::{Toolbar, ToolbarState};
 
 const BG_COLOR: Color = Color::rgb24(0xfb_fb_fb);
 const TOOLBAR_POSITION: Point = Point::new(8., 8.);
+const PALETTE_POSITION: Point = Point::new(8., 48.);
 
 pub(crate) const MIN_POINT_DISTANCE: f64 = 3.0;
 
+/// Payload: the tool name ("select" or "pen"), as in `ToolbarItem::name`.
+const SWITCH_TOOL: Selector = Selector::new("bez-editor.switch-tool");
+/// Payload: none.
+const DELETE_PATH: Selector = Selector::new("bez-editor.delete-path");
+
 struct Canvas {
     toolbar: WidgetPod<ToolbarState, Toolbar>,
+    palette: WidgetPod<PaletteState, CommandPalette>,
+    /// The last position `MouseMoved` reported, used to resolve which path
+    /// point (if any) is hovered against this frame's hitboxes. `None`
+    /// until the mouse has moved at least once.
+    hover_pos: Option<Point>,
+    /// Set when a `KeyDown(Escape)` closes the palette, so the `KeyUp`
+    /// half of that same key press — delivered as its own, separate event —
+    /// doesn't fall through to the "delete the in-progress path" shortcut
+    /// below.
+    escape_dismissed_palette: bool,
 }
 
 impl Canvas {
     fn new() -> Self {
+        let mut registry = CommandRegistry::new();
+        registry.register("Select Tool", || Command::new(SWITCH_TOOL, "select".to_string()));
+        registry.register("Pen Tool", || Command::new(SWITCH_TOOL, "pen".to_string()));
+        registry.register("Delete Path", || Command::new(DELETE_PATH, ()));
+        registry.register("Save As SVG...", || {
+            let opts = FileDialogOptions::new()
+                .allowed_types(vec![FileSpec::SVG])
+                .default_name("bezier.svg");
+            Command::new(commands::SHOW_SAVE_PANEL, opts)
+        });
+        registry.register("Open SVG...", || {
+            let opts = FileDialogOptions::new().allowed_types(vec![FileSpec::SVG]);
+            Command::new(commands::SHOW_OPEN_PANEL, opts)
+        });
+
         Canvas {
             toolbar: WidgetPod::new(Toolbar::default()),
+            palette: WidgetPod::new(CommandPalette::new(registry)),
+            hover_pos: None,
+            escape_dismissed_palette: false,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum ActiveTool {
+    Select(Select),
+    Pen(Pen),
+}
+
+impl ActiveTool {
+    fn for_name(name: &str) -> ActiveTool {
+        match name {
+            "select" => ActiveTool::Select(Select::new()),
+            "pen" => ActiveTool::Pen(Pen::new()),
+            other => panic!("unknown tool '{}'", other),
+        }
+    }
+
+    /// The marquee rectangle currently being dragged out by the select
+    /// tool, if any; used by `Canvas::paint` to draw the rubber band.
+    fn marquee_rect(&self) -> Option<Rect> {
+        match self {
+            ActiveTool::Select(select) => select.marquee_rect(),
+            ActiveTool::Pen(_) => None,
+        }
+    }
+}
+
+impl Tool for ActiveTool {
+    fn event(&mut self, data: &mut Contents, event: &Event) -> bool {
+        match self {
+            ActiveTool::Select(select) => select.event(data, event),
+            ActiveTool::Pen(pen) => pen.event(data, event),
+        }
+    }
+}
+
+/// Undo history for the canvas's `Contents`. A type alias rather than a
+/// wrapper struct: `UndoStack<Contents>` is already exactly the shape this
+/// editor needs.
+type History = UndoStack<Contents>;
+
 #[derive(Debug, Clone)]
 struct CanvasState {
-    tool: Pen,
+    tool: ActiveTool,
     /// The paths in the canvas
     contents: Contents,
     toolbar: ToolbarState,
+    history: History,
+    palette: PaletteState,
 }
 
 impl CanvasState {
     fn new() -> Self {
         CanvasState {
-            tool: Pen::new(),
+            tool: ActiveTool::for_name(ToolbarState::basic().selected_item().name.as_str()),
             contents: Contents::default(),
             toolbar: ToolbarState::basic(),
+            history: History::new(),
+            palette: PaletteState::default(),
         }
     }
 
@@ -75,10 +157,10 @@ impl CanvasState {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SelectionId {
-    path_idx: usize,
-    point_id: PointId,
+    pub(crate) path_idx: usize,
+    pub(crate) point_id: PointId,
 }
 
 impl SelectionId {
@@ -87,6 +169,12 @@ impl SelectionId {
     }
 }
 
+impl Data for SelectionId {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Contents {
     next_path_id: usize,
@@ -153,6 +241,81 @@ impl Contents {
         self.active_path_mut().unwrap().update_for_drag(end);
         //eprintln!("SEL: {:?}", self.selection.first());
     }
+
+    pub(crate) fn selection(&self) -> &[SelectionId] {
+        &self.selection
+    }
+
+    /// Replace the selection with every point whose position falls inside
+    /// `rect`, across all paths.
+    pub(crate) fn select_in_rect(&mut self, rect: Rect) {
+        let mut hits = Vec::new();
+        for (path_idx, path) in self.paths.iter().enumerate() {
+            for p in path.points() {
+                if rect.contains(p.point) {
+                    hits.push(SelectionId::new(path_idx, p.id));
+                }
+            }
+        }
+        *self.selection_mut() = hits;
+    }
+
+    fn is_selected(&self, path_idx: usize, point_id: PointId) -> bool {
+        self.selection
+            .iter()
+            .any(|sel| sel.path_idx == path_idx && sel.point_id == point_id)
+    }
+
+    /// Add or remove `id` from the selection, for shift-click.
+    pub(crate) fn toggle_selected(&mut self, id: SelectionId) {
+        if self.is_selected(id.path_idx, id.point_id) {
+            self.selection_mut()
+                .retain(|sel| !(sel.path_idx == id.path_idx && sel.point_id == id.point_id));
+        } else {
+            self.selection_mut().push(id);
+        }
+    }
+
+    pub(crate) fn select_only(&mut self, id: SelectionId) {
+        *self.selection_mut() = vec![id];
+    }
+
+    /// Find the point under `pos`, if any, searching topmost path first.
+    pub(crate) fn point_near(&self, pos: Point, max_dist: f64) -> Option<SelectionId> {
+        for (path_idx, path) in self.paths.iter().enumerate().rev() {
+            for p in path.points() {
+                if (p.point - pos).hypot() <= max_dist {
+                    return Some(SelectionId::new(path_idx, p.id));
+                }
+            }
+        }
+        None
+    }
+
+    /// Move every selected point by `delta`, across however many paths it
+    /// spans.
+    pub(crate) fn nudge_selection(&mut self, delta: Vec2) {
+        let selection = self.selection.clone();
+        let paths = self.paths_mut();
+        for sel in selection.iter() {
+            if let Some(path) = paths.get_mut(sel.path_idx) {
+                path.move_points(&[sel.point_id], delta);
+            }
+        }
+    }
+
+    pub(crate) fn to_svg(&self) -> String {
+        svg::to_svg_document(&self.paths)
+    }
+
+    pub(crate) fn from_svg(src: &str) -> Result<Contents, SvgError> {
+        let paths = svg::from_svg_document(src)?;
+        Ok(Contents {
+            next_path_id: paths.len(),
+            paths: Arc::new(paths),
+            selection: Arc::new(Vec::new()),
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -174,6 +337,7 @@ impl Data for CanvasState {
         self.contents.same(&other.contents)
             && self.toolbar.same(&other.toolbar)
             && self.tool == other.tool
+            && self.palette.same(&other.palette)
     }
 }
 
@@ -192,9 +356,17 @@ impl Widget<CanvasState> for Canvas {
         _env: &Env,
     ) {
         paint_ctx.render_ctx.clear(BG_COLOR);
-        draw_paths(&data.contents.paths, &data.contents.selection, paint_ctx);
+        draw_paths(
+            &data.contents.paths,
+            &data.contents.selection,
+            data.tool.marquee_rect(),
+            self.hover_pos,
+            paint_ctx,
+        );
         self.toolbar
             .paint_with_offset(paint_ctx, &data.toolbar, _env);
+        self.palette
+            .paint_with_offset(paint_ctx, &data.palette, _env);
     }
 
     fn layout(
@@ -207,9 +379,31 @@ impl Widget<CanvasState> for Canvas {
         let toolbar_size = self.toolbar.layout(ctx, bc, &data.toolbar, env);
         self.toolbar
             .set_layout_rect(Rect::from_origin_size(TOOLBAR_POSITION, toolbar_size));
+        let palette_size = self.palette.layout(ctx, bc, &data.palette, env);
+        self.palette
+            .set_layout_rect(Rect::from_origin_size(PALETTE_POSITION, palette_size));
         bc.max()
     }
 
+    fn after_layout(&mut self, ctx: &mut LayoutCtx, data: &CanvasState, env: &Env) {
+        // Path points are the canvas's own interactive regions; `Tool`
+        // never sees a `LayoutCtx` (it only runs during `event`), so
+        // `Canvas` registers one hitbox per point directly, keyed by
+        // `PointId::to_hitbox_id`, for `Canvas::paint` to hover-test
+        // against below. They're registered before the toolbar and
+        // palette so a point under either one loses the hit-test tie,
+        // matching paint order (points are painted first, underneath).
+        for (path_idx, path) in data.contents.paths.iter().enumerate() {
+            for p in path.points() {
+                let r = MIN_POINT_DISTANCE * 2.0;
+                let rect = Rect::new(p.point.x - r, p.point.y - r, p.point.x + r, p.point.y + r);
+                ctx.insert_hitbox_with_id(rect, p.id.to_hitbox_id(path_idx));
+            }
+        }
+        self.toolbar.after_layout(ctx, &data.toolbar, env, 0);
+        self.palette.after_layout(ctx, &data.palette, env, 1);
+    }
+
     fn event(
         &mut self,
         event: &Event,
@@ -217,15 +411,104 @@ impl Widget<CanvasState> for Canvas {
         data: &mut CanvasState,
         _env: &Env,
     ) -> Option<Action> {
+        if let Event::MouseMoved(mouse) = event {
+            self.hover_pos = Some(mouse.pos);
+        }
+
+        // Coalesce the snapshots the active tool's drag produces into a
+        // single undo step: take it at `Mouse::Down`, and only commit it
+        // in `past` at `Mouse::Up` if something actually changed.
+        if let Event::MouseDown(_) = event {
+            data.history.begin_transaction(data.contents.clone());
+        }
+
+        // The palette is modal: while it's open it sees every event before
+        // the toolbar or the active tool do.
+        if data.palette.visible {
+            let is_escape_keydown = matches!(event, Event::KeyDown(key) if key.key_code == KeyCode::Escape);
+            self.palette.event(event, ctx, &mut data.palette, _env);
+            if is_escape_keydown && !data.palette.visible {
+                self.escape_dismissed_palette = true;
+            }
+            if ctx.is_handled() {
+                return None;
+            }
+        }
+
         // first check for top-level commands
         match event {
-            Event::KeyUp(key) if key.key_code == KeyCode::Escape => {
+            Event::KeyDown(key) if key.mods.meta && key.mods.shift && key.key_code == KeyCode::KeyP => {
+                data.palette.visible = true;
+                ctx.set_handled();
+            }
+            Event::Command(command) if command.selector == SWITCH_TOOL => {
+                if let Some(name) = command.get_object::<String>() {
+                    if let Some(idx) = data.toolbar.idx_for_name(name) {
+                        data.toolbar.set_selected(idx);
+                    }
+                    data.tool = ActiveTool::for_name(name);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(command) if command.selector == DELETE_PATH => {
+                data.history.record(data.contents.clone());
                 data.remove_top_path();
                 ctx.set_handled();
             }
+            Event::KeyDown(key) if key.mods.meta && key.mods.shift && key.key_code == KeyCode::KeyZ => {
+                if let Some(prev) = data.history.redo(data.contents.clone()) {
+                    data.contents = prev;
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.mods.meta && key.key_code == KeyCode::KeyZ => {
+                if let Some(prev) = data.history.undo(data.contents.clone()) {
+                    data.contents = prev;
+                }
+                ctx.set_handled();
+            }
+            Event::KeyUp(key) if key.key_code == KeyCode::Escape => {
+                if self.escape_dismissed_palette {
+                    self.escape_dismissed_palette = false;
+                } else {
+                    data.history.record(data.contents.clone());
+                    data.remove_top_path();
+                }
+                ctx.set_handled();
+            }
             Event::KeyUp(key) if data.toolbar.idx_for_key(key).is_some() => {
                 let idx = data.toolbar.idx_for_key(key).unwrap();
                 data.toolbar.set_selected(idx);
+                data.tool = ActiveTool::for_name(data.toolbar.selected_item().name.as_str());
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.mods.meta && key.key_code == KeyCode::KeyS => {
+                let opts = FileDialogOptions::new()
+                    .allowed_types(vec![FileSpec::SVG])
+                    .default_name("bezier.svg");
+                ctx.submit_command(Command::new(commands::SHOW_SAVE_PANEL, opts), None);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if key.mods.meta && key.key_code == KeyCode::KeyO => {
+                let opts = FileDialogOptions::new().allowed_types(vec![FileSpec::SVG]);
+                ctx.submit_command(Command::new(commands::SHOW_OPEN_PANEL, opts), None);
+                ctx.set_handled();
+            }
+            Event::Command(command) if command.selector == commands::SAVE_FILE => {
+                if let Some(info) = command.get_object::<FileInfo>() {
+                    let _ = std::fs::write(&info.path, data.contents.to_svg());
+                }
+                ctx.set_handled();
+            }
+            Event::Command(command) if command.selector == commands::OPEN_FILE => {
+                if let Some(info) = command.get_object::<FileInfo>() {
+                    match std::fs::read_to_string(&info.path).map_err(|e| e.to_string()).and_then(|src| {
+                        Contents::from_svg(&src).map_err(|e| e.to_string())
+                    }) {
+                        Ok(contents) => data.contents = contents,
+                        Err(err) => eprintln!("failed to open '{}': {}", info.path.display(), err),
+                    }
+                }
                 ctx.set_handled();
             }
             other => {
@@ -238,6 +521,10 @@ impl Widget<CanvasState> for Canvas {
         if ctx.is_handled() | tool.event(contents, event) {
             ctx.invalidate();
         }
+
+        if let Event::MouseUp(_) = event {
+            data.history.commit(&data.contents);
+        }
         None
     }
 
@@ -263,6 +550,7 @@ impl Widget<CanvasState> for Canvas {
             ctx.invalidate();
         }
         self.toolbar.update(ctx, &new.toolbar, _env);
+        self.palette.update(ctx, &new.palette, _env);
     }
 }
 
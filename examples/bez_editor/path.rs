@@ -0,0 +1,109 @@
+//! A single bezier path: an ordered list of points, each optionally
+//! carrying a trailing control handle used to draw a curve (rather than a
+//! straight line) to the next point.
+
+use druid::kurbo::{Point, Vec2};
+use druid::Data;
+
+/// Identifies a point within a single `Path`. Stable across edits to other
+/// points, so a `SelectionId` built from it stays valid as the path grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointId(usize);
+
+impl PointId {
+    /// A crate-local numeric identity for this point, combined with its
+    /// owning path's index so it can key a per-frame `Hitbox` (point ids
+    /// are only unique within a single `Path`). The top bit is set so the
+    /// result never equals a `WidgetPod` hitbox id, which count up from 1.
+    pub(crate) fn to_hitbox_id(self, path_idx: usize) -> u64 {
+        (1 << 63) | ((path_idx as u64) << 32) | self.0 as u64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPoint {
+    pub id: PointId,
+    pub point: Point,
+    /// The control handle for the curve segment leaving this point, in
+    /// absolute coordinates. `None` means that segment is a straight line.
+    pub trailing_handle: Option<Point>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    points: Vec<PathPoint>,
+    next_id: usize,
+}
+
+impl Path {
+    pub fn new(start: Point) -> Path {
+        let mut path = Path {
+            points: Vec::new(),
+            next_id: 0,
+        };
+        path.push_point(start);
+        path
+    }
+
+    fn push_point(&mut self, point: Point) -> PointId {
+        let id = PointId(self.next_id);
+        self.next_id += 1;
+        self.points.push(PathPoint {
+            id,
+            point,
+            trailing_handle: None,
+        });
+        id
+    }
+
+    pub fn points(&self) -> &[PathPoint] {
+        &self.points
+    }
+
+    pub fn last_point_id(&self) -> PointId {
+        self.points.last().expect("path is never empty").id
+    }
+
+    pub fn point_for_id(&self, id: PointId) -> Option<Point> {
+        self.points.iter().find(|p| p.id == id).map(|p| p.point)
+    }
+
+    pub fn append_point(&mut self, point: Point) -> PointId {
+        self.push_point(point)
+    }
+
+    /// Called while dragging after placing a point: sets (or clears, if
+    /// `end` is back at the point itself) the trailing handle of the last
+    /// point, so the segment about to be drawn curves towards `end`.
+    pub fn update_for_drag(&mut self, end: Point) {
+        let last = self.points.last_mut().expect("path is never empty");
+        if (end - last.point).hypot() < 0.5 {
+            last.trailing_handle = None;
+        } else {
+            last.trailing_handle = Some(end);
+        }
+    }
+
+    /// Move every point whose id is in `ids` by `delta`, along with any
+    /// trailing handle (so a curve's shape is preserved while dragging).
+    pub fn move_points(&mut self, ids: &[PointId], delta: Vec2) {
+        for p in self.points.iter_mut() {
+            if ids.contains(&p.id) {
+                p.point += delta;
+                if let Some(handle) = p.trailing_handle.as_mut() {
+                    *handle += delta;
+                }
+            }
+        }
+    }
+}
+
+// Structural equality rather than an `Arc`-backed shortcut: `Path` is the
+// thing that actually changes on every edit, so there's no cheaper
+// approximation to make here, unlike the `Arc<Vec<Path>>` wrapper that
+// holds it.
+impl Data for Path {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
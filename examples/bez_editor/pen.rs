@@ -0,0 +1,63 @@
+//! The pen tool: click to start or extend a path, drag to curve the
+//! segment just placed.
+
+use druid::kurbo::Point;
+use druid::Event;
+
+use crate::{Contents, Mouse, Tool, MIN_POINT_DISTANCE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Pen {
+    drag_start: Option<Point>,
+}
+
+impl Pen {
+    pub fn new() -> Pen {
+        Pen::default()
+    }
+
+    fn mouse_event(&mut self, event: &Event) -> Option<Mouse> {
+        match event {
+            Event::MouseDown(mouse) => {
+                self.drag_start = Some(mouse.pos);
+                Some(Mouse::Down(mouse.pos))
+            }
+            Event::MouseMoved(mouse) => self.drag_start.map(|start| Mouse::Drag {
+                start,
+                current: mouse.pos,
+            }),
+            Event::MouseUp(mouse) => {
+                self.drag_start = None;
+                Some(Mouse::Up(mouse.pos))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Tool for Pen {
+    fn event(&mut self, data: &mut Contents, event: &Event) -> bool {
+        let mouse = match self.mouse_event(event) {
+            Some(mouse) => mouse,
+            None => return false,
+        };
+        match mouse {
+            Mouse::Down(point) => {
+                let too_close = data
+                    .active_path()
+                    .and_then(|path| path.point_for_id(path.last_point_id()))
+                    .map(|last| (last - point).hypot() < MIN_POINT_DISTANCE)
+                    .unwrap_or(false);
+                if !too_close {
+                    data.add_point(point);
+                }
+                true
+            }
+            Mouse::Drag { start, current } => {
+                data.update_for_drag(start, current);
+                true
+            }
+            Mouse::Up(_) => true,
+        }
+    }
+}
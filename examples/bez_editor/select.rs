@@ -0,0 +1,131 @@
+//! The selection tool: click, shift-click, rubber-band marquee, and
+//! dragging the current selection around.
+
+use druid::kurbo::{Point, Rect, Vec2};
+use druid::{Event, KeyCode};
+
+use crate::{Contents, Mouse, Tool, MIN_POINT_DISTANCE};
+
+const NUDGE: f64 = 1.0;
+const NUDGE_LARGE: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    /// Dragging out a marquee from `origin` to the current mouse position.
+    Marquee { origin: Point, current: Point },
+    /// Dragging the current selection; `last` is the previous mouse
+    /// position, so each move event only has to apply the incremental
+    /// delta.
+    Selection { last: Point },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Select {
+    drag: Option<Drag>,
+    drag_start: Option<Point>,
+}
+
+impl Select {
+    pub fn new() -> Select {
+        Select::default()
+    }
+
+    pub fn marquee_rect(&self) -> Option<Rect> {
+        match self.drag {
+            Some(Drag::Marquee { origin, current }) => Some(Rect::from_points(origin, current)),
+            _ => None,
+        }
+    }
+
+    fn mouse_event(&mut self, event: &Event) -> Option<Mouse> {
+        match event {
+            Event::MouseDown(mouse) => {
+                self.drag_start = Some(mouse.pos);
+                Some(Mouse::Down(mouse.pos))
+            }
+            Event::MouseMoved(mouse) => self.drag_start.map(|start| Mouse::Drag {
+                start,
+                current: mouse.pos,
+            }),
+            Event::MouseUp(mouse) => {
+                self.drag_start = None;
+                Some(Mouse::Up(mouse.pos))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Tool for Select {
+    fn event(&mut self, data: &mut Contents, event: &Event) -> bool {
+        if let Event::KeyDown(key) = event {
+            let delta = if key.mods.shift { NUDGE_LARGE } else { NUDGE };
+            let nudge = match key.key_code {
+                KeyCode::ArrowUp => Some(Vec2::new(0.0, -delta)),
+                KeyCode::ArrowDown => Some(Vec2::new(0.0, delta)),
+                KeyCode::ArrowLeft => Some(Vec2::new(-delta, 0.0)),
+                KeyCode::ArrowRight => Some(Vec2::new(delta, 0.0)),
+                _ => None,
+            };
+            if let Some(delta) = nudge {
+                data.nudge_selection(delta);
+                return true;
+            }
+            return false;
+        }
+
+        // Shift-click toggles a point in/out of the selection without
+        // starting a drag; everything else goes through the generic
+        // down/drag/up flow below.
+        if let Event::MouseDown(mouse) = event {
+            if mouse.mods.shift {
+                if let Some(id) = data.point_near(mouse.pos, MIN_POINT_DISTANCE * 2.0) {
+                    data.toggle_selected(id);
+                    return true;
+                }
+            }
+        }
+
+        let mouse = match self.mouse_event(event) {
+            Some(mouse) => mouse,
+            None => return false,
+        };
+
+        match mouse {
+            Mouse::Down(pos) => {
+                match data.point_near(pos, MIN_POINT_DISTANCE * 2.0) {
+                    Some(id) => {
+                        if !data.selection().contains(&id) {
+                            data.select_only(id);
+                        }
+                        self.drag = Some(Drag::Selection { last: pos });
+                    }
+                    None => {
+                        self.drag = Some(Drag::Marquee {
+                            origin: pos,
+                            current: pos,
+                        });
+                    }
+                }
+                true
+            }
+            Mouse::Drag { current, .. } => match self.drag {
+                Some(Drag::Marquee { origin, .. }) => {
+                    self.drag = Some(Drag::Marquee { origin, current });
+                    data.select_in_rect(Rect::from_points(origin, current));
+                    true
+                }
+                Some(Drag::Selection { last }) => {
+                    data.nudge_selection(current - last);
+                    self.drag = Some(Drag::Selection { last: current });
+                    true
+                }
+                None => false,
+            },
+            Mouse::Up(_) => {
+                self.drag = None;
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Serializing `Path`s to and from SVG `<path d="...">` data.
+//!
+//! Only a single trailing control handle per point is kept in our model
+//! (see `path::PathPoint`), which represents a *quadratic* bezier control.
+//! SVG only has a compact syntax for *cubic* curves, so on export we
+//! convert the quadratic control `q` to the equivalent cubic controls via
+//! the standard identity `c1 = p0 + 2/3(q - p0)`, `c2 = p1 + 2/3(q - p1)`,
+//! and on import we invert that same identity from `c1`. Paths that came
+//! from some other tool's genuinely-cubic curves will still parse, just
+//! with the curve approximated from its first control point.
+
+use druid::kurbo::Point;
+
+use crate::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+    /// No path data at all (the document had no `<path>` elements).
+    Empty,
+    /// The `d` attribute was malformed, or ran out of arguments partway
+    /// through a command.
+    BadPathData(String),
+    Xml(String),
+}
+
+impl std::fmt::Display for SvgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SvgError::Empty => write!(f, "document contained no <path> elements"),
+            SvgError::BadPathData(s) => write!(f, "malformed path data: {}", s),
+            SvgError::Xml(s) => write!(f, "could not parse XML: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+pub(crate) fn to_svg_document(paths: &[Path]) -> String {
+    let mut out = String::new();
+    out.push_str("<svg xmlns=\"http://www.w3.org/2000/svg\">\n");
+    for path in paths {
+        out.push_str("  <path d=\"");
+        out.push_str(&path_data(path));
+        out.push_str("\"/>\n");
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn path_data(path: &Path) -> String {
+    let points = path.points();
+    let mut data = String::new();
+    if points.is_empty() {
+        return data;
+    }
+    data.push_str(&format!("M{},{}", points[0].point.x, points[0].point.y));
+    for window in points.windows(2) {
+        let (prev, next) = (window[0], window[1]);
+        match prev.trailing_handle {
+            Some(q) => {
+                let c1 = prev.point + (q - prev.point) * (2.0 / 3.0);
+                let c2 = next.point + (q - next.point) * (2.0 / 3.0);
+                data.push_str(&format!(
+                    " C{},{} {},{} {},{}",
+                    c1.x, c1.y, c2.x, c2.y, next.point.x, next.point.y
+                ));
+            }
+            None => data.push_str(&format!(" L{},{}", next.point.x, next.point.y)),
+        }
+    }
+    data
+}
+
+pub(crate) fn from_svg_document(src: &str) -> Result<Vec<Path>, SvgError> {
+    let doc = roxmltree::Document::parse(src).map_err(|e| SvgError::Xml(e.to_string()))?;
+    let mut paths = Vec::new();
+    for node in doc.descendants().filter(|n| n.has_tag_name("path")) {
+        if let Some(d) = node.attribute("d") {
+            paths.push(parse_path_data(d)?);
+        }
+    }
+    if paths.is_empty() {
+        return Err(SvgError::Empty);
+    }
+    Ok(paths)
+}
+
+fn parse_path_data(d: &str) -> Result<Path, SvgError> {
+    let mut tok = Tokenizer::new(d);
+
+    let cmd = tok
+        .next_command()
+        .ok_or_else(|| SvgError::BadPathData("expected a moveto".into()))?;
+    if !matches!(cmd, 'M' | 'm') {
+        return Err(SvgError::BadPathData("path data must start with a moveto".into()));
+    }
+    let mut cur = tok.pair()?;
+    let mut path = Path::new(cur);
+
+    // The command letter may be omitted for repeats of L/C; `last_cmd`
+    // tracks which command an unlabelled group of numbers belongs to. Per
+    // the SVG spec, an implicit lineto after a moveto takes the moveto's
+    // relativity ('m' implies 'l', 'M' implies 'L'), not always absolute.
+    let mut last_cmd = if cmd == 'm' { 'l' } else { 'L' };
+    while !tok.at_end() {
+        let cmd = tok.peek_command().unwrap_or(last_cmd);
+        if tok.peek_command().is_some() {
+            tok.advance_command();
+        }
+        last_cmd = cmd;
+
+        match cmd {
+            'L' | 'l' => {
+                cur = tok.relative_pair(cur, cmd == 'l')?;
+                path.append_point(cur);
+            }
+            'C' | 'c' => {
+                let relative = cmd == 'c';
+                let c1 = tok.relative_pair(cur, relative)?;
+                let _c2 = tok.relative_pair(cur, relative)?;
+                let end = tok.relative_pair(cur, relative)?;
+                // Invert the quad/cubic identity used by `path_data`,
+                // using the first control point: q = p0 + 3/2(c1 - p0).
+                let q = cur + (c1 - cur) * 1.5;
+                path.update_for_drag(q);
+                path.append_point(end);
+                cur = end;
+            }
+            'Z' | 'z' => break,
+            other => return Err(SvgError::BadPathData(format!("unsupported command '{}'", other))),
+        }
+    }
+    Ok(path)
+}
+
+/// A minimal hand-rolled tokenizer for the `d` attribute's mini-language:
+/// a command letter followed by whitespace/comma-separated numbers, with
+/// an implicit repeat of the previous command when a number group isn't
+/// preceded by a new letter.
+struct Tokenizer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(src: &'a str) -> Self {
+        Tokenizer { rest: src.trim() }
+    }
+
+    fn skip_sep(&mut self) {
+        self.rest = self.rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_sep();
+        self.rest.is_empty()
+    }
+
+    fn peek_command(&mut self) -> Option<char> {
+        self.skip_sep();
+        self.rest.chars().next().filter(|c| c.is_ascii_alphabetic())
+    }
+
+    fn advance_command(&mut self) {
+        self.skip_sep();
+        let mut chars = self.rest.chars();
+        chars.next();
+        self.rest = chars.as_str();
+    }
+
+    fn next_command(&mut self) -> Option<char> {
+        let c = self.peek_command()?;
+        self.advance_command();
+        Some(c)
+    }
+
+    fn number(&mut self) -> Result<f64, SvgError> {
+        self.skip_sep();
+        let mut len = 0;
+        for (i, c) in self.rest.char_indices() {
+            let is_numeric = c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E';
+            let is_leading_sign = (c == '-' || c == '+') && i == 0;
+            if is_numeric || is_leading_sign {
+                len = i + c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        if len == 0 {
+            return Err(SvgError::BadPathData("expected a number".into()));
+        }
+        let (num, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        num.parse()
+            .map_err(|_| SvgError::BadPathData(format!("invalid number '{}'", num)))
+    }
+
+    fn pair(&mut self) -> Result<Point, SvgError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Point::new(x, y))
+    }
+
+    fn relative_pair(&mut self, origin: Point, relative: bool) -> Result<Point, SvgError> {
+        let p = self.pair()?;
+        Ok(if relative { origin + (p - Point::ORIGIN) } else { p })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(path: &Path) -> Vec<Point> {
+        path.points().iter().map(|p| p.point).collect()
+    }
+
+    #[test]
+    fn parses_absolute_lineto_with_implicit_repeats() {
+        let path = parse_path_data("M0,0 L10,0 20,0 30,0").unwrap();
+        assert_eq!(points(&path), vec![Point::new(0., 0.), Point::new(10., 0.), Point::new(20., 0.), Point::new(30., 0.)]);
+    }
+
+    #[test]
+    fn implicit_lineto_after_relative_moveto_stays_relative() {
+        // Per the SVG spec, an unlabelled coordinate group following a
+        // relative `m` is itself a relative `l`, not an absolute `L`.
+        let path = parse_path_data("m10,10 5,0 5,0").unwrap();
+        assert_eq!(points(&path), vec![Point::new(10., 10.), Point::new(15., 10.), Point::new(20., 10.)]);
+    }
+
+    #[test]
+    fn implicit_lineto_after_absolute_moveto_stays_absolute() {
+        let path = parse_path_data("M10,10 20,10 30,10").unwrap();
+        assert_eq!(points(&path), vec![Point::new(10., 10.), Point::new(20., 10.), Point::new(30., 10.)]);
+    }
+
+    #[test]
+    fn rejects_data_not_starting_with_moveto() {
+        assert!(parse_path_data("L10,0").is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_command() {
+        assert!(parse_path_data("M0,0 Q5,5 10,0").is_err());
+    }
+
+    #[test]
+    fn to_svg_document_round_trips_through_from_svg_document() {
+        let mut path = Path::new(Point::new(0., 0.));
+        path.append_point(Point::new(10., 0.));
+        path.update_for_drag(Point::new(20., 10.));
+        path.append_point(Point::new(30., 0.));
+
+        let doc = to_svg_document(&[path.clone()]);
+        let parsed = from_svg_document(&doc).unwrap();
+        assert_eq!(parsed.len(), 1);
+
+        // The quad/cubic control-point conversion isn't bit-exact in
+        // either direction, just algebraically invertible, so compare
+        // with a tolerance rather than `==`.
+        let close = |a: Point, b: Point| (a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9;
+        let (original, roundtripped) = (path.points(), parsed[0].points());
+        assert_eq!(original.len(), roundtripped.len());
+        for (a, b) in original.iter().zip(roundtripped) {
+            assert!(close(a.point, b.point), "{:?} != {:?}", a.point, b.point);
+            match (a.trailing_handle, b.trailing_handle) {
+                (None, None) => {}
+                (Some(ha), Some(hb)) => assert!(close(ha, hb), "{:?} != {:?}", ha, hb),
+                handles => panic!("trailing handles differ: {:?}", handles),
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! The tool-picker strip painted in the top-left corner of the canvas.
+
+use druid::kurbo::{Rect, Size};
+use druid::piet::{Color, RenderContext};
+use druid::{
+    BaseState, BoxConstraints, Data, Env, Event, EventCtx, KeyCode, KeyEvent, LayoutCtx, PaintCtx,
+    UpdateCtx, Widget,
+};
+
+const ITEM_SIZE: Size = Size::new(32., 32.);
+const IDLE_COLOR: Color = Color::rgb24(0xe0_e0_e0);
+const SELECTED_COLOR: Color = Color::rgb24(0x4a_90_d9);
+const FOCUS_RING_COLOR: Color = Color::rgb24(0x4a_90_d9);
+
+#[derive(Debug, Clone)]
+pub struct ToolbarItem {
+    pub name: String,
+    pub key: KeyCode,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolbarState {
+    items: Vec<ToolbarItem>,
+    selected_idx: usize,
+}
+
+impl ToolbarState {
+    /// The toolbar shipped with the bezier editor: selection and pen.
+    pub fn basic() -> ToolbarState {
+        ToolbarState {
+            items: vec![
+                ToolbarItem {
+                    name: "select".into(),
+                    key: KeyCode::KeyV,
+                },
+                ToolbarItem {
+                    name: "pen".into(),
+                    key: KeyCode::KeyA,
+                },
+            ],
+            selected_idx: 1,
+        }
+    }
+
+    pub fn idx_for_key(&self, key: &KeyEvent) -> Option<usize> {
+        self.items.iter().position(|item| item.key == key.key_code)
+    }
+
+    pub fn idx_for_name(&self, name: &str) -> Option<usize> {
+        self.items.iter().position(|item| item.name == name)
+    }
+
+    pub fn selected_idx(&self) -> usize {
+        self.selected_idx
+    }
+
+    pub fn selected_item(&self) -> &ToolbarItem {
+        &self.items[self.selected_idx]
+    }
+
+    pub fn set_selected(&mut self, idx: usize) {
+        assert!(idx < self.items.len());
+        self.selected_idx = idx;
+    }
+}
+
+impl Data for ToolbarState {
+    fn same(&self, other: &Self) -> bool {
+        self.selected_idx == other.selected_idx
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Toolbar {
+    /// Whether the toolbar currently holds keyboard focus, tracked from
+    /// `Event::FocusChanged` since `paint` only sees `PaintCtx`, which
+    /// doesn't know about focus.
+    focused: bool,
+}
+
+impl Widget<ToolbarState> for Toolbar {
+    fn paint(&mut self, paint_ctx: &mut PaintCtx, _base: &BaseState, data: &ToolbarState, _env: &Env) {
+        for (idx, _item) in data.items.iter().enumerate() {
+            let rect = item_rect(idx);
+            let color = if idx == data.selected_idx {
+                SELECTED_COLOR
+            } else {
+                IDLE_COLOR
+            };
+            let brush = paint_ctx.render_ctx.solid_brush(color);
+            paint_ctx.render_ctx.fill(rect, &brush);
+        }
+        if self.focused {
+            let size = Size::new(ITEM_SIZE.width, ITEM_SIZE.height * data.items.len() as f64);
+            let ring = Rect::from_origin_size((0., 0.), size);
+            let ring_brush = paint_ctx.render_ctx.solid_brush(FOCUS_RING_COLOR);
+            paint_ctx.render_ctx.stroke(ring, &ring_brush, 2.0);
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        _bc: &BoxConstraints,
+        data: &ToolbarState,
+        _env: &Env,
+    ) -> Size {
+        Size::new(ITEM_SIZE.width, ITEM_SIZE.height * data.items.len() as f64)
+    }
+
+    fn after_layout(&mut self, ctx: &mut LayoutCtx, data: &ToolbarState, _env: &Env) {
+        // A single hitbox for the whole strip is enough for now; individual
+        // button hit-testing can split this up once the toolbar grows
+        // click-to-select support.
+        let size = Size::new(ITEM_SIZE.width, ITEM_SIZE.height * data.items.len() as f64);
+        ctx.insert_hitbox(Rect::from_origin_size((0., 0.), size));
+    }
+
+    fn event(
+        &mut self,
+        event: &Event,
+        ctx: &mut EventCtx,
+        _data: &mut ToolbarState,
+        _env: &Env,
+    ) -> Option<druid::Action> {
+        if let Event::FocusChanged { gained, lost } = event {
+            if *gained && ctx.is_focused() {
+                self.focused = true;
+                ctx.invalidate();
+            } else if *lost && ctx.was_focused() {
+                self.focused = false;
+                ctx.invalidate();
+            }
+        }
+        None
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old: Option<&ToolbarState>, _new: &ToolbarState, _env: &Env) {}
+
+    /// Lets Tab/Shift-Tab cycle focus to the toolbar, which paints a focus
+    /// ring (see `paint`) so the feature is visible in the one example that
+    /// wires it up so far.
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+fn item_rect(idx: usize) -> Rect {
+    Rect::from_origin_size((0., idx as f64 * ITEM_SIZE.height), ITEM_SIZE)
+}